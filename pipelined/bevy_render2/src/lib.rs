@@ -13,22 +13,64 @@ pub mod view;
 use std::ops::{Deref, DerefMut};
 
 pub use once_cell;
-use wgpu::BackendBit;
+use wgpu::{Adapter, BackendBit, Features, Limits, PowerPreference};
 
 use crate::{
     camera::CameraPlugin,
     mesh::MeshPlugin,
     render_graph::RenderGraph,
     render_phase::DrawFunctions,
-    renderer::render_system,
+    renderer::{render_system, RenderInitError},
     texture::ImagePlugin,
     view::{ViewPlugin, WindowRenderPlugin},
 };
 use bevy_app::{App, Plugin};
 use bevy_ecs::prelude::*;
 
-#[derive(Default)]
-pub struct RenderPlugin;
+/// Configures and initializes the wgpu renderer backend used by the render sub-app.
+pub struct RenderPlugin {
+    /// The wgpu backends that may be used to find a suitable adapter. Defaults to
+    /// [`BackendBit::PRIMARY`], which selects Vulkan, Metal or DX12 depending on platform. Set
+    /// this to a single backend (e.g. `BackendBit::VULKAN`) to debug backend-specific issues.
+    pub backends: BackendBit,
+    /// Whether to prefer a high-performance or low-power adapter. Defaults to
+    /// [`PowerPreference::HighPerformance`]; switch to [`PowerPreference::LowPower`] to prefer
+    /// integrated/battery-friendly GPUs.
+    pub power_preference: PowerPreference,
+    /// Extra features to request from the device, on top of wgpu's defaults. Set this when a
+    /// custom shader relies on a feature that isn't requested anywhere else.
+    pub device_features: Features,
+    /// The resource limits to request from the device. Defaults to [`Limits::default()`].
+    pub device_limits: Limits,
+    /// An already-created adapter to initialize the renderer with, bypassing adapter selection
+    /// via `backends`/`power_preference` entirely.
+    pub adapter: Option<Adapter>,
+    /// If no suitable adapter or device can be found (common in CI, headless servers, or VMs
+    /// without a GPU), skip registering the render sub-app instead of panicking. This leaves
+    /// `App` running headless, which is useful for test harnesses and other tooling built on
+    /// this crate. Defaults to `true`; set this to `false` if a missing renderer should be a
+    /// hard error for your application instead.
+    pub allow_headless_fallback: bool,
+    /// Not read by [`RenderPlugin::build`], so setting it has no effect. Whatever its value, the
+    /// render sub-app runs `Extract`, `Prepare`, `Queue`, `PhaseSort`, `Render` and `Cleanup`
+    /// synchronously, one after another, within the main app's update; render-world work for one
+    /// frame does not overlap with the main world's update of the next frame. Defaults to `false`.
+    pub pipelined_rendering: bool,
+}
+
+impl Default for RenderPlugin {
+    fn default() -> Self {
+        Self {
+            backends: BackendBit::PRIMARY,
+            power_preference: PowerPreference::HighPerformance,
+            device_features: Features::empty(),
+            device_limits: Limits::default(),
+            adapter: None,
+            allow_headless_fallback: true,
+            pipelined_rendering: false,
+        }
+    }
+}
 
 /// The names of the default App stages
 #[derive(Debug, Hash, PartialEq, Eq, Clone, StageLabel)]
@@ -78,40 +120,53 @@ impl DerefMut for RenderWorld {
 #[derive(Default)]
 struct ScratchRenderWorld(World);
 
-/// The  App World. This is only available as a resource during the Queue step.
-#[derive(Default)]
-pub struct AppWorld(World);
-
-impl Deref for AppWorld {
+/// A read-only borrow of the main app world, made available as a resource on the render world
+/// during the Queue step. Queue systems can look up original or already-extracted app-world data
+/// (e.g. with `Res<MainWorld>`) without the render sub-app ever taking ownership of the `World`:
+/// `app_world` is never moved out of the main app, just borrowed for the duration of the Queue
+/// stage.
+///
+/// This holds a raw pointer rather than a `&World` so it can be inserted into `render_app.world`
+/// as an ordinary `'static` resource. The `unsafe impl Send + Sync` is sound because the pointee
+/// is only ever read (there is no `DerefMut`) and the borrow does not outlive the `queue` call
+/// that creates it.
+pub struct MainWorld(std::ptr::NonNull<World>);
+
+// SAFETY: `MainWorld` only exposes shared (`&World`) access to the world it points to, and its
+// only instance is dropped by `queue` before `app_world` is used again on the main thread.
+unsafe impl Send for MainWorld {}
+unsafe impl Sync for MainWorld {}
+
+impl Deref for MainWorld {
     type Target = World;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
-    }
-}
-
-impl DerefMut for AppWorld {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+        // SAFETY: see the `unsafe impl Send + Sync` justification above.
+        unsafe { self.0.as_ref() }
     }
 }
 
-/// A "scratch" world used to avoid allocating new worlds every frame when
-// swapping out the Render World.
-#[derive(Default)]
-struct ScratchAppWorld(World);
-
 impl Plugin for RenderPlugin {
     fn build(&self, app: &mut App) {
-        let (instance, device, queue) =
-            futures_lite::future::block_on(renderer::initialize_renderer(
-                BackendBit::PRIMARY,
-                &wgpu::RequestAdapterOptions {
-                    power_preference: wgpu::PowerPreference::HighPerformance,
-                    ..Default::default()
-                },
-                &wgpu::DeviceDescriptor::default(),
-            ));
+        let renderer_init = futures_lite::future::block_on(renderer::initialize_renderer(
+            self.backends,
+            self.power_preference,
+            self.device_features,
+            self.device_limits,
+            self.adapter.as_ref(),
+        ));
+
+        let (instance, device, queue) = match renderer_init {
+            Ok(renderer) => renderer,
+            Err(err) if self.allow_headless_fallback => {
+                bevy_utils::tracing::error!(
+                    "Failed to initialize the renderer ({}), running without a render sub-app",
+                    err
+                );
+                return;
+            }
+            Err(err) => panic!("Failed to initialize the renderer: {}", err),
+        };
         app.insert_resource(device.clone())
             .insert_resource(queue.clone())
             .init_resource::<ScratchRenderWorld>();
@@ -135,8 +190,7 @@ impl Plugin for RenderPlugin {
             .insert_resource(device)
             .insert_resource(queue)
             .init_resource::<RenderGraph>()
-            .init_resource::<DrawFunctions>()
-            .init_resource::<ScratchAppWorld>();
+            .init_resource::<DrawFunctions>();
 
         app.add_sub_app(render_app, move |app_world, render_app| {
             // reserve all existing app entities for use in render_app
@@ -163,35 +217,9 @@ impl Plugin for RenderPlugin {
             prepare.run(&mut render_app.world);
 
             // queue
-            queue_worldsurgery(app_world, render_app);
-            // let queue = render_app
-            //     .schedule
-            //     .get_stage_mut::<SystemStage>(&RenderStage::Queue)
-            //     .unwrap();
-            // queue.run(&mut render_app.world);
-
-            // phase sort
-            let phase_sort = render_app
-                .schedule
-                .get_stage_mut::<SystemStage>(&RenderStage::PhaseSort)
-                .unwrap();
-            phase_sort.run(&mut render_app.world);
+            queue(app_world, render_app);
 
-            // render
-            let render = render_app
-                .schedule
-                .get_stage_mut::<SystemStage>(&RenderStage::Render)
-                .unwrap();
-            render.run(&mut render_app.world);
-
-            // cleanup
-            let cleanup = render_app
-                .schedule
-                .get_stage_mut::<SystemStage>(&RenderStage::Cleanup)
-                .unwrap();
-            cleanup.run(&mut render_app.world);
-
-            render_app.world.clear_entities();
+            run_phase_sort_through_cleanup(render_app);
         });
 
         app.add_plugin(WindowRenderPlugin)
@@ -223,28 +251,45 @@ fn extract(app_world: &mut World, render_app: &mut App) {
     extract.apply_buffers(&mut render_app.world);
 }
 
-fn queue_worldsurgery(app_world: &mut World, render_app: &mut App) {
+/// Runs the Queue stage, making the main world available to its systems through the read-only
+/// [`MainWorld`] resource instead of handing the render sub-app the whole `World`.
+fn queue(app_world: &mut World, render_app: &mut App) {
     let queue = render_app
         .schedule
         .get_stage_mut::<SystemStage>(&RenderStage::Queue)
         .unwrap();
 
-    // temporarily add the app world to the render world as a resource
-    let scratch_world = render_app
+    // Lend `app_world` to the render world as a read-only `MainWorld` resource for the duration
+    // of the Queue stage, without moving it out of the main app.
+    render_app
         .world
-        .remove_resource::<ScratchAppWorld>()
-        .unwrap();
-    let app_world_temp = std::mem::replace(app_world, scratch_world.0);
-    render_app.world.insert_resource(AppWorld(app_world_temp));
+        .insert_resource(MainWorld(std::ptr::NonNull::from(&*app_world)));
 
     queue.run(&mut render_app.world);
+    queue.apply_buffers(&mut render_app.world);
 
-    // add the app world back to the  app
-    let app_world_temp = render_app.world.remove_resource::<AppWorld>().unwrap();
-    let scratch_world: World = std::mem::replace(app_world, app_world_temp.0);
-    render_app
-        .world
-        .insert_resource(ScratchAppWorld(scratch_world));
+    render_app.world.remove_resource::<MainWorld>();
+}
+
+/// Runs the PhaseSort, Render and Cleanup stages synchronously on `render_app.world`.
+fn run_phase_sort_through_cleanup(render_app: &mut App) {
+    let phase_sort = render_app
+        .schedule
+        .get_stage_mut::<SystemStage>(&RenderStage::PhaseSort)
+        .unwrap();
+    phase_sort.run(&mut render_app.world);
+
+    let render = render_app
+        .schedule
+        .get_stage_mut::<SystemStage>(&RenderStage::Render)
+        .unwrap();
+    render.run(&mut render_app.world);
+
+    let cleanup = render_app
+        .schedule
+        .get_stage_mut::<SystemStage>(&RenderStage::Cleanup)
+        .unwrap();
+    cleanup.run(&mut render_app.world);
 
-    queue.apply_buffers(app_world);
+    render_app.world.clear_entities();
 }
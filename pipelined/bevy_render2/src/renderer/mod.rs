@@ -0,0 +1,131 @@
+use std::{ops::Deref, sync::Arc};
+
+use wgpu::{Adapter, BackendBit, Features, Limits, PowerPreference};
+
+use crate::render_graph::RenderGraph;
+use bevy_ecs::world::{Mut, World};
+
+/// An error returned when no GPU adapter or device matching a [`crate::RenderPlugin`]'s
+/// configuration could be obtained.
+#[derive(Debug)]
+pub enum RenderInitError {
+    /// No adapter matching the requested `backends`/`power_preference` could be found.
+    NoSuitableAdapter,
+    /// An adapter was found, but it could not provide a device with the requested
+    /// features/limits.
+    DeviceRequestFailed(wgpu::RequestDeviceError),
+}
+
+impl std::fmt::Display for RenderInitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RenderInitError::NoSuitableAdapter => write!(
+                f,
+                "unable to find a GPU adapter matching the requested backends/power preference"
+            ),
+            RenderInitError::DeviceRequestFailed(err) => {
+                write!(f, "failed to request a device from the adapter: {}", err)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RenderInitError {}
+
+/// The GPU instance created for the configured backends. Unless an [`Adapter`] was supplied to
+/// [`initialize_renderer`], the adapter that [`RenderDevice`]/[`RenderQueue`] were created from
+/// was requested from this instance.
+pub struct RenderInstance(pub wgpu::Instance);
+
+/// The GPU device responsible for the creation of most rendering and compute resources.
+#[derive(Clone)]
+pub struct RenderDevice(Arc<wgpu::Device>);
+
+impl From<Arc<wgpu::Device>> for RenderDevice {
+    fn from(device: Arc<wgpu::Device>) -> Self {
+        Self(device)
+    }
+}
+
+impl Deref for RenderDevice {
+    type Target = wgpu::Device;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// The queue used to submit GPU commands created from [`RenderDevice`].
+#[derive(Clone)]
+pub struct RenderQueue(Arc<wgpu::Queue>);
+
+impl From<Arc<wgpu::Queue>> for RenderQueue {
+    fn from(queue: Arc<wgpu::Queue>) -> Self {
+        Self(queue)
+    }
+}
+
+impl Deref for RenderQueue {
+    type Target = wgpu::Queue;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Requests an [`Adapter`] (unless one is already supplied) matching `backends` and
+/// `power_preference`, then a [`wgpu::Device`]/[`wgpu::Queue`] from it supporting
+/// `device_features` and `device_limits`.
+pub async fn initialize_renderer(
+    backends: BackendBit,
+    power_preference: PowerPreference,
+    device_features: Features,
+    device_limits: Limits,
+    adapter: Option<&Adapter>,
+) -> Result<(RenderInstance, RenderDevice, RenderQueue), RenderInitError> {
+    let instance = wgpu::Instance::new(backends);
+
+    let requested_adapter;
+    let adapter = match adapter {
+        Some(adapter) => adapter,
+        None => {
+            requested_adapter = instance
+                .request_adapter(&wgpu::RequestAdapterOptions {
+                    power_preference,
+                    compatible_surface: None,
+                })
+                .await
+                .ok_or(RenderInitError::NoSuitableAdapter)?;
+            &requested_adapter
+        }
+    };
+
+    let (device, queue) = adapter
+        .request_device(
+            &wgpu::DeviceDescriptor {
+                label: None,
+                features: device_features,
+                limits: device_limits,
+            },
+            None,
+        )
+        .await
+        .map_err(RenderInitError::DeviceRequestFailed)?;
+
+    let device = Arc::new(device);
+    let queue = Arc::new(queue);
+
+    Ok((
+        RenderInstance(instance),
+        RenderDevice::from(device),
+        RenderQueue::from(queue),
+    ))
+}
+
+/// Executes the [`RenderGraph`] against the render world. Scheduled as an exclusive system in
+/// [`crate::RenderStage::Render`].
+pub fn render_system(world: &mut World) {
+    world.resource_scope(|world, mut graph: Mut<RenderGraph>| {
+        graph.update(world);
+    });
+}